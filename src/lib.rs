@@ -1,15 +1,17 @@
 #![no_std]
 
 use core::alloc::Layout;
-use core::mem;
+use core::mem::{self, MaybeUninit};
 use core::ops::{Deref, DerefMut};
 use core::ptr::{self, NonNull};
 use core::slice;
 
 pub mod sys;
 pub mod raw;
+pub mod pod;
 
 use raw::PbufPtr;
+use pod::Pod;
 
 #[repr(transparent)]
 /// A safe wrapper around the underlying [`sys::pbuf`] struct. This struct is
@@ -54,12 +56,118 @@ impl Pbuf {
         unsafe { slice::from_raw_parts_mut(self.bytes_mut_ptr(), self.len()) }
     }
 
+    /// Reads a [`Pod`] value out of the payload at `offset`, without
+    /// requiring the payload to be naturally aligned for `T`. Returns
+    /// `None` if `offset + size_of::<T>()` is out of bounds, rather than
+    /// reading out of bounds.
+    pub fn read_at<T: Pod>(&self, offset: usize) -> Option<T> {
+        let size = mem::size_of::<T>();
+
+        if offset.checked_add(size)? > self.len() {
+            return None;
+        }
+
+        unsafe { Some(ptr::read_unaligned(self.bytes_ptr().add(offset).cast())) }
+    }
+
+    /// Writes a [`Pod`] value into the payload at `offset`, without
+    /// requiring the payload to be naturally aligned for `T`. Returns
+    /// `None` if `offset + size_of::<T>()` is out of bounds, rather than
+    /// writing out of bounds.
+    pub fn write_at<T: Pod>(&mut self, offset: usize, value: &T) -> Option<()> {
+        let size = mem::size_of::<T>();
+
+        if offset.checked_add(size)? > self.len() {
+            return None;
+        }
+
+        unsafe {
+            ptr::write_unaligned(self.bytes_mut_ptr().add(offset).cast(), *value);
+        }
+
+        Some(())
+    }
+
     /// The next buffer in the chain.
     pub fn next(&self) -> Option<&Pbuf> {
         NonNull::new(self.raw.next).map(|ptr| {
             unsafe { ptr.cast::<Pbuf>().as_ref() }
         })
     }
+
+    /// The total length of this buffer and every buffer after it in the
+    /// chain.
+    pub fn tot_len(&self) -> usize {
+        self.raw.tot_len.into()
+    }
+
+    /// Iterates over this buffer and every buffer after it in the chain.
+    pub fn chain_iter(&self) -> PbufChain<'_> {
+        PbufChain { next: Some(self) }
+    }
+
+    /// Copies bytes out of this buffer's chain into `dst`, walking each
+    /// segment in turn, until `dst` is full or the chain is exhausted.
+    /// Returns the number of bytes copied, which may be less than
+    /// `dst.len()` if the chain is shorter.
+    pub fn copy_out(&self, dst: &mut [u8]) -> usize {
+        let mut written = 0;
+
+        for pbuf in self.chain_iter() {
+            if written >= dst.len() {
+                break;
+            }
+
+            let src = pbuf.bytes();
+            let n = src.len().min(dst.len() - written);
+            dst[written..][..n].copy_from_slice(&src[..n]);
+            written += n;
+        }
+
+        written
+    }
+
+    /// Copies bytes from `src` into this buffer's chain, walking each
+    /// segment in turn, until `src` is exhausted or the chain is full.
+    /// Returns the number of bytes copied, which may be less than
+    /// `src.len()` if the chain is shorter.
+    pub fn copy_in(&mut self, src: &[u8]) -> usize {
+        let mut read = 0;
+        let mut current: *mut sys::pbuf = &mut self.raw;
+
+        while read < src.len() {
+            let Some(current_ref) = NonNull::new(current) else { break };
+
+            // SAFETY: each pbuf in the chain is distinct and we only ever
+            // hold one `&mut Pbuf` to it at a time.
+            let pbuf = unsafe { Pbuf::from_mut_ref(current_ref.cast().as_mut()) };
+
+            let dst = pbuf.bytes_mut();
+            let n = dst.len().min(src.len() - read);
+            dst[..n].copy_from_slice(&src[read..][..n]);
+            read += n;
+
+            current = pbuf.raw.next;
+        }
+
+        read
+    }
+}
+
+/// Iterator over a [`Pbuf`] chain, from a given buffer to the end of the
+/// chain. See [`Pbuf::chain_iter`].
+pub struct PbufChain<'a> {
+    next: Option<&'a Pbuf>,
+}
+
+impl<'a> Iterator for PbufChain<'a> {
+    type Item = &'a Pbuf;
+
+    fn next(&mut self) -> Option<&'a Pbuf> {
+        let current = self.next.take()?;
+        self.next = current.next();
+        Some(current)
+    }
 }
 
 /// A shared reference to [`Pbuf`]. Reference counted, immutable.
@@ -127,6 +235,14 @@ pub enum AllocatePbufError {
     AllocationFailed,
 }
 
+/// Returned by [`PbufUninit::try_copied_from_slice`] when the source
+/// slice's length does not match the pbuf's allocated length.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthMismatch {
+    pub expected: usize,
+    pub actual: usize,
+}
+
 impl PbufUninit {
     pub fn allocate(layer: sys::pbuf_layer, type_: sys::pbuf_type, length: usize)
         -> Result<Self, AllocatePbufError>
@@ -195,11 +311,168 @@ impl PbufUninit {
         }
     }
 
+    /// Like [`copied_from_slice`], but returns the still-uninitialized
+    /// buffer back in the error variant instead of panicking on mismatched
+    /// length. Use this when the expected length comes from untrusted
+    /// input, such as a size field read off the network.
+    ///
+    /// [`copied_from_slice`]: PbufUninit::copied_from_slice
+    pub fn try_copied_from_slice(mut self, slice: &[u8]) -> Result<PbufMut, (Self, LengthMismatch)> {
+        if slice.len() != self.len() {
+            return Err((self, LengthMismatch { expected: self.len(), actual: slice.len() }));
+        }
+
+        unsafe {
+            ptr::copy(slice.as_ptr(), self.bytes_mut_ptr(), self.len());
+            Ok(self.assume_init())
+        }
+    }
+
+    /// Copies up to `min(slice.len(), self.len())` bytes from `slice` into
+    /// the payload, then shrinks the pbuf to the number of bytes actually
+    /// copied. Never panics, regardless of how `slice` and the allocated
+    /// length compare.
+    pub fn copy_prefix_from_slice(mut self, slice: &[u8]) -> PbufMut {
+        let copy_len = slice.len().min(self.len());
+
+        unsafe {
+            ptr::copy(slice.as_ptr(), self.bytes_mut_ptr(), copy_len);
+            let pbuf = raw::PbufPtr::as_mut_ptr(&self.ptr);
+            sys::pbuf_realloc(pbuf, copy_len as u16);
+            self.assume_init()
+        }
+    }
+
     pub fn bytes_mut_ptr(&mut self) -> *mut u8 {
         self.ptr.bytes_mut_ptr()
     }
 
+    /// Borrows the uninitialized payload for reading, without ever forming
+    /// a reference to the underlying `sys::pbuf` struct itself.
+    pub fn bytes_uninit(&self) -> &[MaybeUninit<u8>] {
+        unsafe {
+            let pbuf = raw::PbufPtr::as_ptr(&self.ptr);
+            let len = usize::from((&raw const (*pbuf).len).read());
+            let payload = (&raw const (*pbuf).payload).read();
+            slice::from_raw_parts(payload.cast(), len)
+        }
+    }
+
+    /// Borrows the uninitialized payload for writing, without ever forming
+    /// a reference to the underlying `sys::pbuf` struct itself. Allows
+    /// incrementally initializing the payload; call [`assume_init`] once
+    /// every byte has been written.
+    ///
+    /// [`assume_init`]: PbufUninit::assume_init
+    pub fn bytes_uninit_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        unsafe {
+            let pbuf = raw::PbufPtr::as_mut_ptr(&self.ptr);
+            let len = usize::from((&raw const (*pbuf).len).read());
+            let payload = (&raw mut (*pbuf).payload).read();
+            slice::from_raw_parts_mut(payload.cast(), len)
+        }
+    }
+
     pub unsafe fn assume_init(self) -> PbufMut {
         PbufMut { ptr: self.ptr }
     }
+
+    /// Begin writing a structured packet into this buffer's payload using a
+    /// cursor-based [`PbufWriter`]. Each write respects the natural
+    /// alignment of the value being written, so mixed-width fields can be
+    /// packed directly without manual padding arithmetic.
+    pub fn writer(self) -> PbufWriter {
+        PbufWriter { pbuf: self, cursor: 0 }
+    }
+}
+
+/// Cursor-based writer for building a structured packet directly into a
+/// [`PbufUninit`]'s payload, respecting each write's natural alignment.
+///
+/// Obtained from [`PbufUninit::writer`]. Call [`finish`](PbufWriter::finish)
+/// once done to shrink the pbuf to the number of bytes actually written and
+/// commit it to a [`PbufMut`].
+pub struct PbufWriter {
+    pbuf: PbufUninit,
+    cursor: usize,
+}
+
+/// Returned by [`PbufWriter`] write methods when the payload does not have
+/// enough room left, accounting for alignment padding, to fit the value
+/// being written.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteOverflow;
+
+impl PbufWriter {
+    /// The number of bytes written so far, including alignment padding.
+    pub fn len(&self) -> usize {
+        self.cursor
+    }
+
+    /// Writes a byte slice at the current cursor position, with no
+    /// alignment requirement. Returns the offset the bytes were written at.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<usize, WriteOverflow> {
+        unsafe { self.write_raw(bytes.as_ptr(), bytes.len(), 1) }
+    }
+
+    /// Writes a single `Copy` value at the current cursor position, aligned
+    /// to `T`'s natural alignment. Returns the offset the value was written
+    /// at.
+    pub fn write_value<T: Copy>(&mut self, value: &T) -> Result<usize, WriteOverflow> {
+        unsafe { self.write_raw((value as *const T).cast(), mem::size_of::<T>(), mem::align_of::<T>()) }
+    }
+
+    /// Writes a slice of `Copy` values at the current cursor position,
+    /// aligned to `T`'s natural alignment. Returns the offset the slice was
+    /// written at.
+    pub fn write_slice<T: Copy>(&mut self, values: &[T]) -> Result<usize, WriteOverflow> {
+        unsafe {
+            self.write_raw(values.as_ptr().cast(), mem::size_of_val(values), mem::align_of::<T>())
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `src` must be valid for reads of `size` bytes.
+    unsafe fn write_raw(&mut self, src: *const u8, size: usize, align: usize)
+        -> Result<usize, WriteOverflow>
+    {
+        let base_ptr = self.pbuf.bytes_mut_ptr();
+        let len = self.pbuf.len();
+
+        // SAFETY: base_ptr.add(self.cursor) never forms a reference, just
+        // an address to compute alignment padding from.
+        let pad = unsafe { base_ptr.add(self.cursor) }.align_offset(align);
+
+        let start = self.cursor.checked_add(pad).ok_or(WriteOverflow)?;
+        let end = start.checked_add(size).ok_or(WriteOverflow)?;
+
+        if end > len {
+            return Err(WriteOverflow);
+        }
+
+        // SAFETY: we never form a reference to the uninitialized
+        // destination, only write into it through a raw pointer, and we've
+        // just checked that [start, end) is in bounds of the payload.
+        unsafe {
+            // zero the alignment padding so `finish` never commits
+            // uninitialized bytes as part of the reported length:
+            ptr::write_bytes(base_ptr.add(self.cursor), 0, pad);
+            ptr::copy_nonoverlapping(src, base_ptr.add(start), size);
+        }
+
+        self.cursor = end;
+        Ok(start)
+    }
+
+    /// Shrinks the pbuf to the number of bytes written so far and returns
+    /// the now-initialized buffer.
+    pub fn finish(self) -> PbufMut {
+        let len = self.cursor;
+        unsafe {
+            let pbuf = raw::PbufPtr::as_mut_ptr(&self.pbuf.ptr);
+            sys::pbuf_realloc(pbuf, len as u16);
+            self.pbuf.assume_init()
+        }
+    }
 }
@@ -0,0 +1,26 @@
+//! Marker trait for types safe to read from or write to raw pbuf payload
+//! bytes.
+
+/// Marker trait for `Copy` types with no padding bytes and no references,
+/// safe to read from or write to arbitrary, possibly misaligned, payload
+/// bytes via [`Pbuf::read_at`](crate::Pbuf::read_at) and
+/// [`Pbuf::write_at`](crate::Pbuf::write_at).
+///
+/// # Safety
+///
+/// Implementors must guarantee that every bit pattern of size
+/// `size_of::<Self>()` is a valid value of `Self`, and that `Self` contains
+/// no padding bytes. This typically holds for `#[repr(C)]` or
+/// `#[repr(packed)]` structs composed entirely of other `Pod` types, such
+/// as network protocol headers.
+pub unsafe trait Pod: Copy {}
+
+macro_rules! impl_pod {
+    ($($t:ty),* $(,)?) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}